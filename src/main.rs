@@ -4,6 +4,9 @@
 extern crate derive_more;
 
 use {
+    ::async_compression::tokio::bufread::{
+        BrotliEncoder, DeflateEncoder, GzipEncoder,
+    },
     ::bytes::{
         BytesMut,
     },
@@ -11,7 +14,8 @@ use {
         Builder, Env,
     },
     ::futures::{
-        future, FutureExt, stream::StreamExt,
+        future, FutureExt, SinkExt,
+        stream::StreamExt,
     },
     ::handlebars::{
         Handlebars,
@@ -31,7 +35,7 @@ use {
         debug, error, info, trace, warn,
     },
     ::percent_encoding::{
-        percent_decode_str,
+        percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC,
     },
     ::serde::{
         Serialize,
@@ -43,15 +47,18 @@ use {
         ops::Not,
         path::{Path, PathBuf},
         pin::Pin,
+        time::{Duration, Instant, SystemTime},
     },
     ::structopt::{
         StructOpt,
     },
     ::tokio::{
         fs::File,
-        io::{AsyncRead, AsyncReadExt},
+        io::{AsyncRead, AsyncReadExt, AsyncSeekExt, BufReader},
         runtime::Runtime,
+        sync::broadcast,
     },
+    ::tokio_tungstenite::tungstenite::Message,
     ::tokio_util::{
         codec::{BytesCodec, FramedRead},
     },
@@ -117,6 +124,44 @@ struct Config {
     /// The root directory for serving files.
     #[structopt(name = "ROOT", parse(from_os_str), default_value = ".")]
     root_dir: PathBuf,
+
+    /// Disable transparent response compression (br / gzip / deflate,
+    /// negotiated from the request's `Accept-Encoding` header). Compression
+    /// is on by default.
+    #[structopt(long = "no-compression")]
+    no_compression: bool,
+
+    /// Minimum body size, in bytes (the file plus, for HTML, the injected
+    /// live-reload script), before a response is compressed. Below
+    /// this threshold the framing overhead outweighs the benefit.
+    #[structopt(long = "compression-threshold", default_value = "1024")]
+    compression_threshold: u64,
+
+    /// `Cache-Control: max-age` (in seconds) sent with file responses. Kept
+    /// small by default so live-reload still sees fresh content; the
+    /// `Last-Modified` / `ETag` validators still allow cheap revalidation.
+    #[structopt(long = "max-age", default_value = "0")]
+    max_age: u64,
+
+    /// A file to serve instead of a 404 (commonly `index.html`), enabling
+    /// client-side-routed single-page apps to deep-link. Resolved relative
+    /// to `ROOT`.
+    #[structopt(long = "fallback", visible_alias = "spa", parse(from_os_str))]
+    fallback: Option<PathBuf>,
+
+    /// Render an HTML directory listing for directory requests that have no
+    /// `index.html`, instead of a 404.
+    #[structopt(long = "dir-listing")]
+    dir_listing: bool,
+}
+
+impl Config {
+    /// Whether responses should be transparently compressed.
+    fn compression_enabled (&self)
+      -> bool
+    {
+        !self.no_compression
+    }
 }
 
 fn run ()
@@ -195,25 +240,119 @@ fn run ()
     Ok(())
 }
 
+/// The debounce window for coalescing a burst of filesystem events (e.g. an
+/// editor writing a temp file and then renaming it over the real one) into a
+/// single reload notification.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(150);
+
 async
 fn spin_ws_server (config: &'_ Config)
   -> ::anyhow::Result<()>
 {
+    // One broadcast channel shared by every connected client: each file-system
+    // change pushes a single notification that every subscriber receives.
+    let (reload_tx, _) = broadcast::channel(16);
+
+    if let Err(e) = spawn_file_watcher(config.root_dir.clone(), reload_tx.clone()) {
+        // Live-reload is a nicety, not a reason to refuse to serve files.
+        error!("failed to start file watcher, live-reload is disabled: {}", e);
+    }
+
+    let listener = ::tokio::net::TcpListener::bind((config.addr.ip(), config.ws_port)).await?;
     loop {
-        let ws =
-            ::tokio_tungstenite::accept_async(
-                ::tokio::net::TcpListener::bind((config.addr.ip(), config.ws_port))
-                    .await?
-                    .accept()
-                    .await?
-                    .0
-            )
-            .await?
-        ;
-        let _ = ::tokio::task::spawn(ws.for_each(|_| async {}));
+        let (stream, _) = listener.accept().await?;
+        let mut reload_rx = reload_tx.subscribe();
+        let _ = ::tokio::task::spawn(async move {
+            let ws = ::tokio_tungstenite::accept_async(stream).await?;
+            let (mut ws_tx, ws_rx) = ws.split();
+            // Drain (and ignore) whatever the client sends us; we only ever push.
+            ::tokio::task::spawn(ws_rx.for_each(|_| future::ready(())));
+            loop {
+                match reload_rx.recv().await {
+                    Ok(()) => {},
+                    // We missed some notifications because we couldn't keep up
+                    // with the broadcast channel; we don't know whether the
+                    // file that changed is still relevant, so force a reload
+                    // rather than silently going quiet for the rest of the
+                    // connection.
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("ws client lagged behind by {} reload(s), reloading anyway", skipped);
+                    },
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                if ws_tx.send(Message::Text("reload".into())).await.is_err() {
+                    break;
+                }
+            }
+            Ok::<_, ::anyhow::Error>(())
+        });
     }
 }
 
+/// Spawn a recursive filesystem watcher over `root_dir` that debounces bursts
+/// of change events and broadcasts a single reload notification per burst.
+fn spawn_file_watcher (root_dir: PathBuf, reload_tx: broadcast::Sender<()>)
+  -> ::anyhow::Result<()>
+{
+    let (event_tx, event_rx) = ::std::sync::mpsc::channel();
+    let mut watcher = ::notify::recommended_watcher(
+        move |res: ::notify::Result<::notify::Event>| {
+            match res {
+                Ok(event) => { let _ = event_tx.send(event); },
+                Err(e) => warn!("file watcher error: {}", e),
+            }
+        }
+    )?;
+    watcher.watch(&root_dir, ::notify::RecursiveMode::Recursive)?;
+
+    // Debouncing blocks on a plain `mpsc::Receiver`, so run it on its own
+    // thread rather than tying up an async worker; the watcher itself must be
+    // kept alive for as long as we want to keep receiving events from it.
+    ::std::thread::spawn(move || {
+        let _watcher = watcher;
+        while let Ok(first) = event_rx.recv() {
+            let mut should_reload = is_relevant_event(&first);
+            let deadline = Instant::now() + DEBOUNCE_WINDOW;
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                match event_rx.recv_timeout(remaining) {
+                    Ok(event) => should_reload |= is_relevant_event(&event),
+                    Err(_) => break,
+                }
+            }
+            if should_reload {
+                // An error here just means no client is connected yet; fine.
+                let _ = reload_tx.send(());
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Whether a filesystem event should trigger a reload.
+///
+/// Filters out dot-files and common editor temp/swap files, so that e.g. a
+/// single "save" in an editor that writes a swap file first doesn't cause
+/// spurious extra reloads.
+fn is_relevant_event (event: &::notify::Event)
+  -> bool
+{
+    event.paths.iter().any(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .map_or(true, |name| {
+                !name.starts_with('.')
+                    && !name.ends_with('~')
+                    && !name.ends_with(".swp")
+                    && !name.ends_with(".swx")
+            })
+    })
+}
+
 /// Create an HTTP Response future for each Request.
 ///
 /// Errors are turned into an appropriate HTTP error response, and never
@@ -222,10 +361,16 @@ async
 fn serve (config: Config, req: Request<Body>)
   -> Response<Body>
 {
+    // `HEAD` is handled by computing the exact same response as `GET` -
+    // including error responses - and then dropping the body, so status/
+    // Content-Length/Content-Type/caching headers all stay in sync with
+    // what `GET` would send, and a `HEAD` response never carries a body.
+    let is_head = req.method() == Method::HEAD;
     // Serve the requested file.
     let resp = serve_or_error(config, req).await;
     // Transform internal errors to error responses.
-    transform_error(resp)
+    let resp = transform_error(resp);
+    if is_head { without_body(resp) } else { resp }
 }
 
 /// Handle all types of requests, but don't deal with transforming internal
@@ -234,7 +379,12 @@ async
 fn serve_or_error (config: Config, req: Request<Body>)
   -> Result<Response<Body>>
 {
-    // This server only supports the GET method. Return an appropriate
+    // `OPTIONS` never reaches `serve_file`: it doesn't name a representation
+    // to serve, just asks what methods are supported.
+    if req.method() == Method::OPTIONS {
+        return make_options_response();
+    }
+    // Only GET and HEAD can be served as files. Return an appropriate
     // response otherwise.
     if let Some(resp) = handle_unsupported_request(&req) {
         return resp;
@@ -243,6 +393,27 @@ fn serve_or_error (config: Config, req: Request<Body>)
     serve_file(&req, &config).await
 }
 
+/// Build the `204 No Content` response for an `OPTIONS` request, advertising
+/// the methods this server supports.
+fn make_options_response ()
+  -> Result<Response<Body>>
+{
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(header::ALLOW, "GET, HEAD, OPTIONS")
+        .body(Body::empty())
+        .map_err(Into::into)
+}
+
+/// Discard a response's body while keeping its status and headers, for
+/// `HEAD` requests.
+fn without_body (resp: Response<Body>)
+  -> Response<Body>
+{
+    let (parts, _body) = resp.into_parts();
+    Response::from_parts(parts, Body::empty())
+}
+
 /// Serve static files from a root directory.
 async
 fn serve_file (
@@ -255,16 +426,62 @@ fn serve_file (
     // to the static file we want to serve - which may be `index.html` for
     // directories - and send a response containing that file.
     if let Some(redir_resp) = try_dir_redirect(req, &root_dir)? {
-        Ok(redir_resp)
-    } else {
-        respond_with_file(
-            &local_path_with_maybe_index(req.uri(), &root_dir)?,
-            config,
-        )
-        .await
+        return Ok(redir_resp);
+    }
+    // The *pre-index* path, i.e. the directory the request actually names.
+    // `local_path_with_maybe_index` below only appends `index.html` when
+    // this directory exists, so checking the post-index path's parent would
+    // wrongly resolve to an existing ancestor (e.g. `root_dir` itself) for a
+    // request naming a directory that doesn't exist at all.
+    let raw_path = local_path_for_request(req.uri(), &root_dir)?;
+    let path = local_path_with_maybe_index(req.uri(), &root_dir)?;
+    if config.dir_listing && raw_path.is_dir() && path.is_file().not() {
+        // Only reachable once the request URI ends in "/" (we returned above
+        // otherwise), so relative links in the listing resolve correctly.
+        return render_dir_listing(req, &raw_path, config).await;
+    }
+    let resp = respond_with_file(req, &path, config).await;
+    // If the file isn't there and a fallback is configured, serve that
+    // instead - e.g. for a single-page app's client-side router - and only
+    // give up and let the 404 page render if the fallback itself is also
+    // missing.
+    match (&resp, &config.fallback) {
+        (Err(Error::Io(io_err)), Some(fallback)) if io_err.kind() == io::ErrorKind::NotFound => {
+            serve_fallback(req, fallback, config).await
+        },
+        _ => resp,
     }
 }
 
+/// Serve the configured `--fallback` file in place of a 404.
+async
+fn serve_fallback (
+    req: &Request<Body>,
+    fallback: &Path,
+    config: &Config,
+) -> Result<Response<Body>>
+{
+    let path = resolve_under_root_dir(fallback, &config.root_dir)?;
+    respond_with_file(req, &path, config).await
+}
+
+/// Join `path` onto `root_dir`, refusing to resolve to anything outside of
+/// it (e.g. via `..` components).
+fn resolve_under_root_dir (path: &Path, root_dir: &Path)
+  -> Result<PathBuf>
+{
+    let joined = root_dir.join(path);
+    let canonical_root = root_dir.canonicalize()?;
+    let canonical_joined = joined.canonicalize()?;
+    if canonical_joined.starts_with(&canonical_root).not() {
+        return Err(Error::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            "path escapes root_dir",
+        )));
+    }
+    Ok(joined)
+}
+
 /// Try to do a 302 redirect for directories.
 ///
 /// If we get a URL without trailing "/" that can be mapped to a directory, then
@@ -315,30 +532,80 @@ fn try_dir_redirect (
 /// will convert it into the appropriate HTTP error response.
 async
 fn respond_with_file (
+    req: &Request<Body>,
     path: &Path,
     config: &Config,
 ) -> Result<Response<Body>>
 {
     let mime_type = file_path_mime(&path);
-    let file = File::open(path).await?;
+    let mut file = File::open(path).await?;
     let meta = file.metadata().await?;
-    let mut len = meta.len();
-    let stream: Pin<Box<dyn AsyncRead + Send>> =
-        if matches!(
-            path.extension(), Some(ext) if ext.eq_ignore_ascii_case("html")
-        )
-        {
+    let len = meta.len();
+
+    let modified = meta.modified()?;
+    let validators = Validators::new(len, modified);
+    if validators.satisfies(req.headers()) {
+        return validators.not_modified_response(config.max_age);
+    }
 
-            let injected_js = format!(
-                include_str!("client_template.html"),
-                port = config.ws_port,
-            );
-            len += injected_js.len() as u64;
-            Box::pin(file.chain(::std::io::Cursor::new(injected_js)))
+    let is_html = matches!(path.extension(), Some(ext) if ext.eq_ignore_ascii_case("html"));
+    // The injected JS must be concatenated *before* any compression below, so
+    // the compressed stream stays self-consistent.
+    let injected_js = is_html.then(|| format!(
+        include_str!("client_template.html"),
+        port = config.ws_port,
+    ));
+    let total_len = len + injected_js.as_ref().map_or(0, |js| js.len() as u64);
+
+    let encoding =
+        if config.compression_enabled()
+            && total_len >= config.compression_threshold
+            && is_compressible_mime(&mime_type)
+        {
+            best_encoding(req.headers())
         } else {
-            Box::pin(file)
+            None
         }
     ;
+    // Range requests are only honored for a plain, uncompressed file body:
+    // both compression and the injected reload script change the body's
+    // length from what a byte range into the on-disk file would mean.
+    let range_supported = !is_html && encoding.is_none();
+
+    let mut status = StatusCode::OK;
+    let mut content_range = None;
+    let mut body_len = total_len;
+    let stream: Pin<Box<dyn AsyncRead + Send>> = if let Some(injected_js) = injected_js {
+        Box::pin(file.chain(::std::io::Cursor::new(injected_js)))
+    } else if let Some(range) = range_supported.then(|| req.headers().get(header::RANGE)).flatten()
+        .and_then(|v| v.to_str().ok())
+    {
+        match parse_range(range, len) {
+            RangeResult::Unsatisfiable => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                    .body(Body::empty())
+                    .map_err(Into::into);
+            },
+            RangeResult::Satisfiable(start, end) => {
+                file.seek(::std::io::SeekFrom::Start(start)).await?;
+                let count = end - start + 1;
+                status = StatusCode::PARTIAL_CONTENT;
+                content_range = Some(format!("bytes {}-{}/{}", start, end, len));
+                body_len = count;
+                Box::pin(file.take(count))
+            },
+        }
+    } else {
+        Box::pin(file)
+    };
+    let stream: Pin<Box<dyn AsyncRead + Send>> = match encoding {
+        Some(Encoding::Brotli) => Box::pin(BrotliEncoder::new(BufReader::new(stream))),
+        Some(Encoding::Gzip) => Box::pin(GzipEncoder::new(BufReader::new(stream))),
+        Some(Encoding::Deflate) => Box::pin(DeflateEncoder::new(BufReader::new(stream))),
+        None => stream,
+    };
 
     // Here's the streaming code.
     // Codecs are how Tokio creates Streams; a FramedRead
@@ -350,12 +617,213 @@ fn respond_with_file (
     let stream = FramedRead::new(stream, codec);
     let stream = stream.map(|b| b.map(BytesMut::freeze));
     let body = Body::wrap_stream(stream);
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_LENGTH, len as u64)
+    let mut builder = Response::builder()
+        .status(status)
         .header(header::CONTENT_TYPE, mime_type.as_ref())
-        .body(body)
-        .map_err(Into::into)
+        .header(header::VARY, "Accept-Encoding")
+        .also(|b| { validators.apply(b, config.max_age); });
+    if range_supported {
+        builder = builder.header(header::ACCEPT_RANGES, "bytes");
+    }
+    if let Some(content_range) = content_range {
+        builder = builder.header(header::CONTENT_RANGE, content_range);
+    }
+    match encoding {
+        // The encoded size isn't known up front, so we let Hyper fall back to
+        // chunked transfer-encoding instead of sending a `Content-Length`.
+        Some(encoding) => builder
+            .header(header::CONTENT_ENCODING, encoding.as_str())
+            .body(body),
+        None => builder
+            .header(header::CONTENT_LENGTH, body_len)
+            .body(body),
+    }
+    .map_err(Into::into)
+}
+
+/// The outcome of validating a `Range` header against a file's length.
+enum RangeResult {
+    /// `(start, end)`, both inclusive, `0 <= start <= end < len`.
+    Satisfiable(u64, u64),
+    Unsatisfiable,
+}
+
+/// Parse a single-range `Range: bytes=start-end` header (also supporting the
+/// `bytes=start-` and suffix `bytes=-N` forms) and validate it against the
+/// resource's length. Multiple ranges and malformed headers are treated as
+/// unsatisfiable.
+fn parse_range (range: &str, len: u64)
+  -> RangeResult
+{
+    (|| {
+        let spec = range.strip_prefix("bytes=")?;
+        if spec.contains(',') {
+            // We only serve a single contiguous range at a time.
+            return None;
+        }
+        let (start_str, end_str) = spec.split_once('-')?;
+        let (start, end) = if start_str.is_empty() {
+            let suffix_len: u64 = end_str.parse().ok()?;
+            (len.saturating_sub(suffix_len), len.checked_sub(1)?)
+        } else {
+            let start: u64 = start_str.parse().ok()?;
+            let end = if end_str.is_empty() {
+                len.checked_sub(1)?
+            } else {
+                end_str.parse().ok()?
+            };
+            (start, end)
+        };
+        (start <= end && start < len).then(|| RangeResult::Satisfiable(start, end.min(len - 1)))
+    })()
+    .unwrap_or(RangeResult::Unsatisfiable)
+}
+
+/// A supported response compression codec, in `br > gzip > deflate`
+/// preference order.
+#[derive(Clone, Copy)]
+enum Encoding {
+    Brotli,
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// The `Content-Encoding` token for this codec.
+    fn as_str (self)
+      -> &'static str
+    {
+        match self {
+            Self::Brotli => "br",
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+}
+
+/// Parse the request's `Accept-Encoding` header and pick the best codec we
+/// support that the client also accepts (a `q=0` explicitly rules a codec
+/// out).
+fn best_encoding (headers: &HeaderMap)
+  -> Option<Encoding>
+{
+    let accept_encoding = headers.get(header::ACCEPT_ENCODING)?.to_str().ok()?;
+    // Whether some entry names `codec` with (`ruled_out`) or without a `q=0`.
+    let has_entry = |codec: &str, ruled_out: bool| -> bool {
+        accept_encoding.split(',').any(|entry| {
+            let mut parts = entry.split(';');
+            let name = parts.next().unwrap_or("").trim();
+            if !name.eq_ignore_ascii_case(codec) {
+                return false;
+            }
+            parts.any(|param| {
+                param.trim().strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    == Some(0.0)
+            }) == ruled_out
+        })
+    };
+    let accepts = |codec: &str| has_entry(codec, false);
+    if accepts("br") {
+        Some(Encoding::Brotli)
+    } else if accepts("gzip") {
+        Some(Encoding::Gzip)
+    } else if accepts("deflate") {
+        Some(Encoding::Deflate)
+    } else if accepts("*") {
+        // The client will take anything it hasn't ruled out; give it our most
+        // preferred such codec.
+        [Encoding::Brotli, Encoding::Gzip, Encoding::Deflate].iter().copied()
+            .find(|encoding| !has_entry(encoding.as_str(), true))
+    } else {
+        None
+    }
+}
+
+/// Whether a MIME type is worth compressing. Already-compressed types
+/// (images, video, audio and compressed archives) are not, since compressing
+/// them again would waste CPU for no benefit.
+fn is_compressible_mime (mime: &::mime::Mime)
+  -> bool
+{
+    if matches!(mime.type_(), ::mime::IMAGE | ::mime::VIDEO | ::mime::AUDIO) {
+        return false;
+    }
+    !matches!(
+        mime.subtype().as_str(),
+        "zip" | "gzip" | "x-gzip" | "x-bzip2" | "x-7z-compressed" | "x-rar-compressed" | "x-brotli"
+    )
+}
+
+/// The cache-validation headers for a file response: a `Last-Modified` date
+/// and a weak `ETag` derived from the file's length and mtime.
+struct Validators {
+    last_modified: SystemTime,
+    etag: String,
+}
+
+impl Validators {
+    fn new (len: u64, last_modified: SystemTime)
+      -> Validators
+    {
+        let mtime_secs = last_modified
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+        ;
+        Validators {
+            last_modified,
+            etag: format!("W/\"{}-{}\"", len, mtime_secs),
+        }
+    }
+
+    /// Whether the request's `If-None-Match` / `If-Modified-Since` headers
+    /// show the client's cached copy is still fresh.
+    ///
+    /// Per RFC 7232, `If-None-Match` takes precedence over
+    /// `If-Modified-Since` when both are present.
+    fn satisfies (&self, headers: &HeaderMap)
+      -> bool
+    {
+        if let Some(inm) = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+            return inm.split(',').any(|tag| {
+                let tag = tag.trim();
+                tag == "*" || tag == self.etag
+            });
+        }
+        if let Some(ims) = headers.get(header::IF_MODIFIED_SINCE).and_then(|v| v.to_str().ok()) {
+            if let Ok(since) = ::httpdate::parse_http_date(ims) {
+                // HTTP-dates only have second resolution, so compare at that
+                // granularity to avoid spuriously treating a fresh copy as stale.
+                let truncate = |t: SystemTime| t
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs())
+                ;
+                return truncate(self.last_modified) <= truncate(since);
+            }
+        }
+        false
+    }
+
+    fn apply (&self, builder: &mut ::http::response::Builder, max_age: u64)
+    {
+        if let Some(headers) = builder.headers_mut() {
+            headers.insert(header::LAST_MODIFIED, HeaderValue::from_str(
+                &::httpdate::fmt_http_date(self.last_modified),
+            ).unwrap());
+            headers.insert(header::ETAG, HeaderValue::from_str(&self.etag).unwrap());
+            headers.insert(header::CACHE_CONTROL, HeaderValue::from_str(
+                &format!("max-age={}", max_age),
+            ).unwrap());
+        }
+    }
+
+    fn not_modified_response (&self, max_age: u64)
+      -> Result<Response<Body>>
+    {
+        let mut builder = Response::builder().status(StatusCode::NOT_MODIFIED);
+        self.apply(&mut builder, max_age);
+        builder.body(Body::empty()).map_err(Into::into)
+    }
 }
 
 /// Get a MIME type based on the file extension.
@@ -434,10 +902,10 @@ fn get_unsupported_request_message (req: &Request<Body>)
   -> Option<Unsupported>
 {
     // https://tools.ietf.org/html/rfc7231#section-6.5.5
-    (req.method() != Method::GET).then(|| Unsupported {
+    matches!(req.method(), &Method::GET | &Method::HEAD).not().then(|| Unsupported {
         code: StatusCode::METHOD_NOT_ALLOWED,
         headers: ::core::iter::once(
-            (header::ALLOW, HeaderValue::from_static("GET")),
+            (header::ALLOW, HeaderValue::from_static("GET, HEAD, OPTIONS")),
         ).collect(),
     })
 }
@@ -526,3 +994,74 @@ fn render_error_html (status: StatusCode)
         body: String::new(),
     })
 }
+
+/// The handlebars template for a directory listing's entry table. `{{name}}`
+/// and `{{href}}` go through handlebars's default HTML-escaping.
+const DIR_LISTING_TEMPLATE: &str = include_str!("dir_listing.html");
+
+/// One row of a directory listing.
+#[derive(Serialize)]
+struct DirEntryRow {
+    href: String,
+    name: String,
+    size: u64,
+    modified: String,
+}
+
+/// The data for [`DIR_LISTING_TEMPLATE`].
+#[derive(Serialize)]
+struct DirListingCfg {
+    entries: Vec<DirEntryRow>,
+}
+
+/// Render an HTML directory listing for `dir`, linking each entry relative
+/// to the current (trailing-slash) request URL.
+async
+fn render_dir_listing (req: &Request<Body>, dir: &Path, config: &Config)
+  -> Result<Response<Body>>
+{
+    let mut read_dir = ::tokio::fs::read_dir(dir).await?;
+    let mut entries = Vec::new();
+    while let Some(entry) = read_dir.next_entry().await? {
+        let meta = entry.metadata().await?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        entries.push((meta.is_dir(), name, meta));
+    }
+    // Directories first, then alphabetically.
+    entries.sort_by(|(a_is_dir, a_name, _), (b_is_dir, b_name, _)| {
+        b_is_dir.cmp(a_is_dir).then_with(|| a_name.cmp(b_name))
+    });
+    let entries = entries.into_iter().map(|(is_dir, name, meta)| {
+        let suffix = if is_dir { "/" } else { "" };
+        let href = format!(
+            "{}{}",
+            utf8_percent_encode(&name, NON_ALPHANUMERIC),
+            suffix,
+        );
+        DirEntryRow {
+            href,
+            name: format!("{}{}", name, suffix),
+            size: meta.len(),
+            modified: meta.modified().ok()
+                .map(::httpdate::fmt_http_date)
+                .unwrap_or_default(),
+        }
+    }).collect();
+
+    let table = Handlebars::new()
+        .render_template(DIR_LISTING_TEMPLATE, &DirListingCfg { entries })
+        .map_err(Error::TemplateRender)?;
+    let mut body = render_html(HtmlCfg {
+        title: format!("Index of {}", req.uri().path()),
+        body: table,
+    })?;
+    // A directory listing is itself an HTML page, and per request #1 every
+    // served HTML page gets the live-reload client injected into it - a
+    // listing is exactly the kind of page a dev is staring at while
+    // adding/removing files, so it shouldn't be a silent exception.
+    body.push_str(&format!(
+        include_str!("client_template.html"),
+        port = config.ws_port,
+    ));
+    html_str_to_response_with_headers(body, StatusCode::OK, HeaderMap::new())
+}